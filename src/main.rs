@@ -2,11 +2,21 @@
 
 use anyhow::{Context, Result};
 use clap::Parser;
-use hound::WavReader;
+use filetime::{set_file_mtime, FileTime};
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use rayon::prelude::*;
+use std::collections::HashSet;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
 use walkdir::WalkDir;
 
+/// Destination paths already claimed by `--no-clobber`, shared across worker threads so
+/// two files racing on the same generated name don't both win it.
+type ReservedPaths = Mutex<HashSet<PathBuf>>;
+
 /// CLI application to filter WAV audio files by duration.
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -26,17 +36,345 @@ struct Args {
     /// Maximum length in milliseconds (default: no limit)
     #[arg(short = 'M', long, default_value_t = u64::MAX)]
     max_length: u64,
+
+    /// Minimum sample rate in Hz (default: no limit)
+    #[arg(long, default_value_t = 0u32)]
+    min_sample_rate: u32,
+
+    /// Maximum sample rate in Hz (default: no limit)
+    #[arg(long, default_value_t = u32::MAX)]
+    max_sample_rate: u32,
+
+    /// Required number of channels (default: any)
+    #[arg(long)]
+    channels: Option<u16>,
+
+    /// Required bit depth, e.g. 16 or 24 (default: any)
+    #[arg(long)]
+    bits_per_sample: Option<u16>,
+
+    /// Write a CSV manifest of every scanned file (relative path, sample rate,
+    /// channels, bit depth, duration in ms, and kept/skipped) to this path
+    #[arg(long)]
+    manifest: Option<PathBuf>,
+
+    /// Number of worker threads to scan and copy with (default: number of CPUs)
+    #[arg(short = 'j', long)]
+    jobs: Option<usize>,
+
+    /// Instead of copying a kept multi-channel file verbatim, de-interleave it into
+    /// one mono WAV per channel (e.g. "clip-L.wav"/"clip-R.wav" for stereo)
+    #[arg(long)]
+    split_channels: bool,
+
+    /// Never overwrite an existing destination file; instead append an incrementing
+    /// suffix (clip.wav, clip_1.wav, clip_2.wav, ...) until a free name is found
+    #[arg(long)]
+    no_clobber: bool,
+
+    /// Walk and log what would be copied (and under what final name) without
+    /// copying any file or writing the manifest; the output directory itself is
+    /// still created if it does not exist
+    #[arg(long)]
+    dry_run: bool,
 }
 
-/// Calculates the duration of a WAV file in milliseconds.
-fn get_duration_ms(path: &Path) -> Result<u64> {
+/// Opens a WAV file and returns its spec together with its duration in milliseconds.
+fn get_spec_and_duration(path: &Path) -> Result<(WavSpec, u64)> {
     let reader = WavReader::open(path)
         .with_context(|| format!("Failed to open WAV file: {}", path.display()))?;
     let spec = reader.spec();
+    // `reader.len()` is the total interleaved sample count across all channels,
+    // so it must be divided by the channel count to get per-channel sample count.
     let num_samples = reader.len() as f64;
-    let duration_sec = num_samples / spec.sample_rate as f64;
+    let duration_sec = num_samples / (spec.sample_rate as f64 * spec.channels as f64);
     let duration_ms = (duration_sec * 1000.0) as u64;
-    Ok(duration_ms)
+    Ok((spec, duration_ms))
+}
+
+/// Returns true if `spec` satisfies all of the format bounds requested on the command line.
+fn matches_spec(spec: &WavSpec, args: &Args) -> bool {
+    if spec.sample_rate < args.min_sample_rate || spec.sample_rate > args.max_sample_rate {
+        return false;
+    }
+    if let Some(channels) = args.channels {
+        if spec.channels != channels {
+            return false;
+        }
+    }
+    if let Some(bits_per_sample) = args.bits_per_sample {
+        if spec.bits_per_sample != bits_per_sample {
+            return false;
+        }
+    }
+    true
+}
+
+/// Quotes a CSV field if it contains a comma, quote, or newline.
+fn csv_field(value: &str) -> String {
+    if value.contains(['"', ',', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Returns the per-channel filename suffix to use when splitting a multi-channel WAV,
+/// mirroring the "-L"/"-R" convention DAWs use for stereo and "-cN" for anything wider.
+fn channel_suffixes(channels: u16) -> Vec<String> {
+    if channels == 2 {
+        vec!["L".to_string(), "R".to_string()]
+    } else {
+        (1..=channels).map(|c| format!("c{c}")).collect()
+    }
+}
+
+/// Computes the final per-channel output paths for splitting a `channels`-wide WAV
+/// named `{base_name}-{suffix}.wav` inside `out_dir`, applying `--no-clobber` renaming
+/// up front so callers can both preview and write the exact same destinations.
+fn split_output_paths(
+    out_dir: &Path,
+    base_name: &str,
+    channels: u16,
+    no_clobber: bool,
+    reserved: &ReservedPaths,
+) -> Vec<PathBuf> {
+    channel_suffixes(channels)
+        .into_iter()
+        .map(|suffix| {
+            let candidate = out_dir.join(format!("{base_name}-{suffix}.wav"));
+            if no_clobber {
+                reserve_non_existent_filename(&candidate, reserved)
+            } else {
+                candidate
+            }
+        })
+        .collect()
+}
+
+/// De-interleaves a multi-channel WAV file into one mono `WavWriter` per channel,
+/// writing to the given (already resolved) `out_paths`, one per channel in order.
+fn split_channels(path: &Path, out_paths: &[PathBuf], spec: WavSpec) -> Result<()> {
+    let mut reader = WavReader::open(path)
+        .with_context(|| format!("Failed to open WAV file: {}", path.display()))?;
+    let mono_spec = WavSpec {
+        channels: 1,
+        ..spec
+    };
+
+    let mut writers = out_paths
+        .iter()
+        .map(|out_path| {
+            WavWriter::create(out_path, mono_spec)
+                .with_context(|| format!("Failed to create WAV file: {}", out_path.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    match spec.sample_format {
+        SampleFormat::Int => {
+            for (i, sample) in reader.samples::<i32>().enumerate() {
+                let sample = sample
+                    .with_context(|| format!("Failed to read sample from: {}", path.display()))?;
+                writers[i % spec.channels as usize].write_sample(sample)?;
+            }
+        }
+        SampleFormat::Float => {
+            for (i, sample) in reader.samples::<f32>().enumerate() {
+                let sample = sample
+                    .with_context(|| format!("Failed to read sample from: {}", path.display()))?;
+                writers[i % spec.channels as usize].write_sample(sample)?;
+            }
+        }
+    }
+
+    for writer in writers {
+        writer.finalize()?;
+    }
+    Ok(())
+}
+
+/// Verifies that `path`'s parent directory resolves (after following symlinks) to
+/// somewhere inside `canonical_base` (already canonicalized by the caller), so a
+/// crafted relative path or symlink target can't cause writes outside the intended
+/// output directory.
+fn is_path_in_directory(path: &Path, canonical_base: &Path) -> Result<bool> {
+    let parent = path.parent().unwrap_or(path);
+    let canonical_parent = parent
+        .canonicalize()
+        .with_context(|| format!("Failed to canonicalize: {}", parent.display()))?;
+    Ok(canonical_parent.starts_with(canonical_base))
+}
+
+/// Returns the first candidate derived from `path` for which `is_free` returns true:
+/// `path` itself, then `path` with an incrementing `_N` suffix inserted before the
+/// extension (`clip.wav`, `clip_1.wav`, `clip_2.wav`, ...).
+fn first_free_candidate(path: &Path, mut is_free: impl FnMut(&Path) -> bool) -> PathBuf {
+    if is_free(path) {
+        return path.to_path_buf();
+    }
+
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("output");
+    let ext = path.extension().and_then(|ext| ext.to_str());
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+
+    let mut suffix = 1u64;
+    loop {
+        let candidate_name = match ext {
+            Some(ext) => format!("{stem}_{suffix}.{ext}"),
+            None => format!("{stem}_{suffix}"),
+        };
+        let candidate = parent.join(candidate_name);
+        if is_free(&candidate) {
+            return candidate;
+        }
+        suffix += 1;
+    }
+}
+
+/// Finds a filename that doesn't exist yet and atomically reserves it in `reserved`,
+/// inserting an incrementing `_N` suffix before the extension (`clip.wav`,
+/// `clip_1.wav`, `clip_2.wav`, ...) until a free one is found. Reserving under the
+/// same lock that checks for collisions keeps two files racing on the same generated
+/// name under parallel processing from both claiming it before either has written to
+/// disk.
+fn reserve_non_existent_filename(path: &Path, reserved: &ReservedPaths) -> PathBuf {
+    let mut reserved = reserved
+        .lock()
+        .expect("reserved output paths lock poisoned");
+    let chosen = first_free_candidate(path, |candidate| {
+        !candidate.exists() && !reserved.contains(candidate)
+    });
+    reserved.insert(chosen.clone());
+    chosen
+}
+
+/// Probes one WAV file, copies it to `args.output` if it is kept, and returns its
+/// manifest row (when `--manifest` was requested). Runs independently per file so it
+/// can be driven from a parallel iterator.
+fn process_file(
+    path: &Path,
+    args: &Args,
+    canonical_output: &Path,
+    reserved: &ReservedPaths,
+    copied_count: &AtomicU64,
+) -> Result<Option<String>> {
+    let rel_path = path
+        .strip_prefix(&args.input)
+        .with_context(|| format!("Failed to compute relative path for: {}", path.display()))?;
+
+    let (spec, duration) = get_spec_and_duration(path)?;
+    let keep =
+        duration >= args.min_length && duration <= args.max_length && matches_spec(&spec, args);
+
+    let mut out_paths: Vec<PathBuf> = Vec::new();
+
+    if keep {
+        let base_out_path = args.output.join(rel_path);
+        let out_dir = base_out_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .to_path_buf();
+        let base_name = base_out_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("output");
+
+        // Resolve the exact destination path(s) up front so the dry-run preview and
+        // the real write always agree, including any --no-clobber renaming.
+        out_paths = if args.split_channels && spec.channels > 1 {
+            split_output_paths(&out_dir, base_name, spec.channels, args.no_clobber, reserved)
+        } else if args.no_clobber {
+            vec![reserve_non_existent_filename(&base_out_path, reserved)]
+        } else {
+            vec![base_out_path]
+        };
+
+        if args.dry_run {
+            for out_path in &out_paths {
+                println!(
+                    "[dry-run] would copy {} -> {}",
+                    path.display(),
+                    out_path.display()
+                );
+            }
+        } else {
+            fs::create_dir_all(out_dir).with_context(|| {
+                format!(
+                    "Failed to create parent directory for: {}",
+                    out_dir.display()
+                )
+            })?;
+
+            for out_path in &out_paths {
+                if !is_path_in_directory(out_path, canonical_output)? {
+                    anyhow::bail!(
+                        "Refusing to write outside output directory: {}",
+                        out_path.display()
+                    );
+                }
+            }
+
+            let src_mtime = FileTime::from_last_modification_time(
+                &fs::metadata(path)
+                    .with_context(|| format!("Failed to read metadata for: {}", path.display()))?,
+            );
+
+            if args.split_channels && spec.channels > 1 {
+                split_channels(path, &out_paths, spec)?;
+            } else {
+                fs::copy(path, &out_paths[0]).with_context(|| {
+                    format!(
+                        "Failed to copy {} to {}",
+                        path.display(),
+                        out_paths[0].display()
+                    )
+                })?;
+            }
+
+            for out_path in &out_paths {
+                set_file_mtime(out_path, src_mtime).with_context(|| {
+                    format!(
+                        "Failed to preserve modification time on: {}",
+                        out_path.display()
+                    )
+                })?;
+            }
+        }
+
+        copied_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    if args.manifest.is_none() {
+        return Ok(None);
+    }
+
+    let output_field = csv_field(
+        &out_paths
+            .iter()
+            .map(|out_path| {
+                out_path
+                    .strip_prefix(&args.output)
+                    .unwrap_or(out_path)
+                    .display()
+            })
+            .map(|display| display.to_string())
+            .collect::<Vec<_>>()
+            .join(";"),
+    );
+
+    Ok(Some(format!(
+        "{},{},{},{},{},{},{}",
+        csv_field(&rel_path.display().to_string()),
+        spec.sample_rate,
+        spec.channels,
+        spec.bits_per_sample,
+        duration,
+        if keep { "kept" } else { "skipped" },
+        output_field,
+    )))
 }
 
 fn main() -> Result<()> {
@@ -57,56 +395,98 @@ fn main() -> Result<()> {
             args.output.display()
         )
     })?;
+    let canonical_output = args.output.canonicalize().with_context(|| {
+        format!(
+            "Failed to canonicalize output directory: {}",
+            args.output.display()
+        )
+    })?;
 
-    let mut copied_count = 0u64;
+    // Collect every candidate file up front so the probe-and-copy work below can be
+    // spread across a bounded worker pool instead of running strictly sequentially.
+    let wav_paths: Vec<PathBuf> = WalkDir::new(&args.input)
+        .follow_links(false)
+        .into_iter()
+        .collect::<walkdir::Result<Vec<_>>>()
+        .with_context(|| "Failed to read directory entry")?
+        .into_iter()
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("wav"))
+        .collect();
 
-    // Walk the input directory recursively
-    for entry in WalkDir::new(&args.input).follow_links(false).into_iter() {
-        let entry = entry.with_context(|| "Failed to read directory entry")?;
-        if !entry.file_type().is_file() {
-            continue;
-        }
+    let jobs = args
+        .jobs
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .context("Failed to build worker thread pool")?;
 
-        let path = entry.path();
-        if path.extension().and_then(|ext| ext.to_str()) != Some("wav") {
-            continue;
-        }
+    let copied_count = AtomicU64::new(0);
+    let reserved_paths: ReservedPaths = Mutex::new(HashSet::new());
+    let results: Vec<Result<Option<String>>> = pool.install(|| {
+        wav_paths
+            .par_iter()
+            .map(|path| {
+                process_file(
+                    path,
+                    &args,
+                    &canonical_output,
+                    &reserved_paths,
+                    &copied_count,
+                )
+            })
+            .collect()
+    });
 
-        let duration = get_duration_ms(path)?;
-        if duration >= args.min_length && duration <= args.max_length {
-            // Compute relative path and target output path
-            let rel_path = path.strip_prefix(&args.input).with_context(|| {
-                format!("Failed to compute relative path for: {}", path.display())
-            })?;
-            let out_path = args.output.join(rel_path);
+    // Surface the first failure only after every file in the batch has been
+    // attempted, so one unreadable file doesn't stop the rest from being scanned.
+    let mut manifest_rows: Vec<String> = Vec::new();
+    for result in results {
+        if let Some(row) = result? {
+            manifest_rows.push(row);
+        }
+    }
 
-            // Ensure parent directories exist
-            if let Some(parent) = out_path.parent() {
-                fs::create_dir_all(parent).with_context(|| {
-                    format!(
-                        "Failed to create parent directory for: {}",
-                        out_path.display()
-                    )
-                })?;
-            }
+    let copied_count = copied_count.load(Ordering::Relaxed);
 
-            // Copy the file
-            fs::copy(path, &out_path).with_context(|| {
+    if let Some(manifest_path) = &args.manifest {
+        if args.dry_run {
+            println!(
+                "[dry-run] would write manifest to {}",
+                manifest_path.display()
+            );
+        } else {
+            let mut manifest_file = fs::File::create(manifest_path).with_context(|| {
                 format!(
-                    "Failed to copy {} to {}",
-                    path.display(),
-                    out_path.display()
+                    "Failed to create manifest file: {}",
+                    manifest_path.display()
                 )
             })?;
-            copied_count += 1;
+            writeln!(
+                manifest_file,
+                "path,sample_rate,channels,bits_per_sample,duration_ms,status,output_path"
+            )?;
+            for row in &manifest_rows {
+                writeln!(manifest_file, "{row}")?;
+            }
         }
     }
 
-    println!(
-        "Filtered and copied {} WAV files to {}",
-        copied_count,
-        args.output.display()
-    );
+    if args.dry_run {
+        println!(
+            "[dry-run] Would filter and copy {} WAV files to {}",
+            copied_count,
+            args.output.display()
+        );
+    } else {
+        println!(
+            "Filtered and copied {} WAV files to {}",
+            copied_count,
+            args.output.display()
+        );
+    }
     Ok(())
 }
 
@@ -117,9 +497,19 @@ mod tests {
 
     /// Helper to create a temporary WAV file with given sample rate and length in samples.
     fn create_temp_wav(sample_rate: u32, num_samples: u32) -> Result<NamedTempFile> {
+        create_temp_wav_with_channels(sample_rate, 1, num_samples)
+    }
+
+    /// Helper to create a temporary WAV file with given sample rate, channel count, and
+    /// length in interleaved samples (i.e. `num_samples / channels` samples per channel).
+    fn create_temp_wav_with_channels(
+        sample_rate: u32,
+        channels: u16,
+        num_samples: u32,
+    ) -> Result<NamedTempFile> {
         let mut file = NamedTempFile::new()?;
         let spec = hound::WavSpec {
-            channels: 1,
+            channels,
             sample_rate,
             bits_per_sample: 16,
             sample_format: hound::SampleFormat::Int,
@@ -134,28 +524,177 @@ mod tests {
     }
 
     #[test]
-    fn test_get_duration_ms() -> Result<()> {
+    fn test_get_spec_and_duration() -> Result<()> {
         // Test with 1 second duration at 44100 Hz (44100 samples)
         let temp_file = create_temp_wav(44100, 44100)?;
-        let duration = get_duration_ms(temp_file.path())?;
+        let (_, duration) = get_spec_and_duration(temp_file.path())?;
         assert_eq!(duration, 1000);
 
         // Test with 500 ms duration (22050 samples)
         let temp_file = create_temp_wav(44100, 22050)?;
-        let duration = get_duration_ms(temp_file.path())?;
+        let (_, duration) = get_spec_and_duration(temp_file.path())?;
         assert_eq!(duration, 500);
 
         // Test with 0 samples (0 ms)
         let temp_file = create_temp_wav(44100, 0)?;
-        let duration = get_duration_ms(temp_file.path())?;
+        let (_, duration) = get_spec_and_duration(temp_file.path())?;
         assert_eq!(duration, 0);
 
         Ok(())
     }
 
     #[test]
-    fn test_get_duration_ms_invalid_file() {
-        let result = get_duration_ms(Path::new("nonexistent.wav"));
+    fn test_get_spec_and_duration_invalid_file() {
+        let result = get_spec_and_duration(Path::new("nonexistent.wav"));
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_get_spec_and_duration_multi_channel() -> Result<()> {
+        // Stereo file: 88200 interleaved samples at 44100 Hz is 1 second per channel,
+        // not 2 seconds.
+        let temp_file = create_temp_wav_with_channels(44100, 2, 88200)?;
+        let (_, duration) = get_spec_and_duration(temp_file.path())?;
+        assert_eq!(duration, 1000);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("a\"b"), "\"a\"\"b\"");
+    }
+
+    #[test]
+    fn test_channel_suffixes() {
+        assert_eq!(channel_suffixes(2), vec!["L", "R"]);
+        assert_eq!(channel_suffixes(1), vec!["c1"]);
+        assert_eq!(channel_suffixes(4), vec!["c1", "c2", "c3", "c4"]);
+    }
+
+    #[test]
+    fn test_split_channels() -> Result<()> {
+        let temp_file = create_temp_wav_with_channels(44100, 2, 4)?;
+        let out_dir = tempfile::tempdir()?;
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+
+        let reserved = Mutex::new(HashSet::new());
+        let out_paths = split_output_paths(out_dir.path(), "clip", spec.channels, false, &reserved);
+        split_channels(temp_file.path(), &out_paths, spec)?;
+
+        assert!(out_dir.path().join("clip-L.wav").exists());
+        assert!(out_dir.path().join("clip-R.wav").exists());
+
+        let left = WavReader::open(out_dir.path().join("clip-L.wav"))?;
+        assert_eq!(left.spec().channels, 1);
+        assert_eq!(left.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_path_in_directory() -> Result<()> {
+        let base = tempfile::tempdir()?;
+        fs::create_dir(base.path().join("nested"))?;
+        let canonical_base = base.path().canonicalize()?;
+
+        assert!(is_path_in_directory(
+            &base.path().join("nested").join("clip.wav"),
+            &canonical_base
+        )?);
+
+        let outside = tempfile::tempdir()?;
+        assert!(!is_path_in_directory(
+            &outside.path().join("clip.wav"),
+            &canonical_base
+        )?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reserve_non_existent_filename_avoids_concurrent_collision() {
+        let dir = tempfile::tempdir().unwrap();
+        let candidate = dir.path().join("clip-L.wav");
+        let reserved = Mutex::new(HashSet::new());
+
+        // Two "racing" resolutions for the same not-yet-written candidate must not
+        // both be handed the same destination.
+        let first = reserve_non_existent_filename(&candidate, &reserved);
+        let second = reserve_non_existent_filename(&candidate, &reserved);
+
+        assert_eq!(first, candidate);
+        assert_eq!(second, dir.path().join("clip-L_1.wav"));
+    }
+
+    #[test]
+    fn test_first_free_candidate() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("clip.wav");
+        let is_free = |candidate: &Path| !candidate.exists();
+
+        // No collision: the original name is returned unchanged.
+        assert_eq!(first_free_candidate(&path, is_free), path);
+
+        fs::write(&path, b"")?;
+        assert_eq!(
+            first_free_candidate(&path, is_free),
+            dir.path().join("clip_1.wav")
+        );
+
+        fs::write(dir.path().join("clip_1.wav"), b"")?;
+        assert_eq!(
+            first_free_candidate(&path, is_free),
+            dir.path().join("clip_2.wav")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_matches_spec() -> Result<()> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+
+        let args = Args::parse_from([
+            "wav-files-filter",
+            "-i",
+            "in",
+            "-o",
+            "out",
+            "--min-sample-rate",
+            "16000",
+            "--max-sample-rate",
+            "16000",
+            "--channels",
+            "1",
+            "--bits-per-sample",
+            "16",
+        ]);
+        assert!(matches_spec(&spec, &args));
+
+        let args = Args::parse_from([
+            "wav-files-filter",
+            "-i",
+            "in",
+            "-o",
+            "out",
+            "--channels",
+            "2",
+        ]);
+        assert!(!matches_spec(&spec, &args));
+
+        Ok(())
+    }
 }